@@ -0,0 +1,8 @@
+//! The Tantivy-backed symbol index: schema, tokenizer and the
+//! background writer that keeps it up to date.
+
+pub mod schema;
+pub mod tokenizer;
+pub mod writer;
+
+pub use writer::{unlock, FileUpdate, IndexHandle, SearchHit};
@@ -0,0 +1,57 @@
+//! The Tantivy schema the symbol index is built against.
+
+use tantivy::schema::{Schema, FAST, INDEXED, STORED, STRING, TEXT};
+
+use super::tokenizer::CODE_TOKENIZER;
+
+/// Field names, shared by the schema builder and the index writer and
+/// search code so the string literals aren't hardcoded twice.
+pub mod fields {
+    pub const SYMBOL_ID: &str = "symbol_id";
+    pub const FILE_PATH: &str = "file_path";
+    pub const NAME: &str = "name";
+    pub const KIND: &str = "kind";
+    pub const MODULE_PATH: &str = "module_path";
+    pub const SIGNATURE: &str = "signature";
+    pub const DOC: &str = "doc";
+    pub const SPAN_START: &str = "span_start";
+    pub const SPAN_END: &str = "span_end";
+}
+
+/// Build the schema used by [`super::writer::IndexHandle`].
+///
+/// `name` and `signature` use the [`CODE_TOKENIZER`] so that a query for
+/// `format user` matches a symbol named `format_user_name`: the tokenizer
+/// splits on `snake_case` and `CamelCase` boundaries and lowercases each
+/// sub-word. `doc` uses Tantivy's default text tokenizer.
+pub fn build_schema() -> Schema {
+    let mut builder = Schema::builder();
+    builder.add_text_field(fields::SYMBOL_ID, STRING | STORED);
+    builder.add_text_field(fields::FILE_PATH, STRING | STORED | FAST);
+    builder.add_text_field(
+        fields::NAME,
+        tantivy::schema::TextOptions::default()
+            .set_indexing_options(
+                tantivy::schema::TextFieldIndexing::default()
+                    .set_tokenizer(CODE_TOKENIZER)
+                    .set_index_option(tantivy::schema::IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored(),
+    );
+    builder.add_text_field(fields::KIND, STRING | STORED | FAST);
+    builder.add_text_field(fields::MODULE_PATH, STRING | STORED | FAST);
+    builder.add_text_field(
+        fields::SIGNATURE,
+        tantivy::schema::TextOptions::default()
+            .set_indexing_options(
+                tantivy::schema::TextFieldIndexing::default()
+                    .set_tokenizer(CODE_TOKENIZER)
+                    .set_index_option(tantivy::schema::IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored(),
+    );
+    builder.add_text_field(fields::DOC, TEXT | STORED);
+    builder.add_u64_field(fields::SPAN_START, STORED | INDEXED);
+    builder.add_u64_field(fields::SPAN_END, STORED | INDEXED);
+    builder.build()
+}
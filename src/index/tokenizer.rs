@@ -0,0 +1,160 @@
+//! A code-aware tokenizer that splits identifiers on `snake_case` and
+//! `CamelCase` boundaries, so a query for `format user` finds a symbol
+//! named `format_user_name`.
+
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// Name this tokenizer is registered under in the index's
+/// [`tantivy::tokenizer::TokenizerManager`].
+pub const CODE_TOKENIZER: &str = "code";
+
+#[derive(Clone, Default)]
+pub struct CodeTokenizer;
+
+impl Tokenizer for CodeTokenizer {
+    type TokenStream<'a> = CodeTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CodeTokenStream {
+        CodeTokenStream {
+            tokens: split_identifier_words(text),
+            cursor: 0,
+        }
+    }
+}
+
+pub struct CodeTokenStream {
+    tokens: Vec<Token>,
+    cursor: usize,
+}
+
+impl TokenStream for CodeTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.cursor >= self.tokens.len() {
+            return false;
+        }
+        self.cursor += 1;
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.cursor - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.cursor - 1]
+    }
+}
+
+/// Split `text` into lowercase sub-words, first on any run of
+/// non-alphanumeric characters, then on `snake_case`/`CamelCase`
+/// boundaries within each alphanumeric run. Byte offsets are preserved
+/// from the original text and positions increment per sub-word, so a
+/// phrase query over the sub-words still lines up.
+fn split_identifier_words(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    let mut word_start = None;
+
+    let chars = text
+        .char_indices()
+        .map(|(i, c)| (i, c.is_alphanumeric()))
+        .chain(std::iter::once((text.len(), false)));
+    for (i, is_word_char) in chars {
+        match (is_word_char, word_start) {
+            (true, None) => word_start = Some(i),
+            (false, Some(start)) => {
+                for (sub_start, sub_end) in camel_snake_boundaries(&text[start..i], start) {
+                    tokens.push(Token {
+                        offset_from: sub_start,
+                        offset_to: sub_end,
+                        position,
+                        text: text[sub_start..sub_end].to_lowercase(),
+                        position_length: 1,
+                    });
+                    position += 1;
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// Given a single run of alphanumeric bytes (no underscores - those were
+/// already split out as word boundaries) starting at `base_offset` in
+/// the original text, return the `(start, end)` byte ranges of its
+/// `camelCase`/`PascalCase`/acronym sub-words, e.g. `HTTPServerError` ->
+/// `HTTP`, `Server`, `Error`.
+fn camel_snake_boundaries(word: &str, base_offset: usize) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.is_empty() {
+        return Vec::new();
+    }
+    let mut boundaries = vec![0];
+    for i in 1..chars.len() {
+        let prev = chars[i - 1];
+        let cur = chars[i];
+        let starts_new_word = (prev.is_lowercase() && cur.is_uppercase())
+            || (prev.is_numeric() != cur.is_numeric())
+            || (cur.is_uppercase()
+                && i + 1 < chars.len()
+                && chars[i + 1].is_lowercase()
+                && prev.is_uppercase());
+        if starts_new_word {
+            boundaries.push(i);
+        }
+    }
+    boundaries.push(chars.len());
+
+    let mut spans = Vec::new();
+    for pair in boundaries.windows(2) {
+        let (start_char, end_char) = (pair[0], pair[1]);
+        if start_char == end_char {
+            continue;
+        }
+        let start_byte = base_offset + chars[..start_char].iter().map(|c| c.len_utf8()).sum::<usize>();
+        let end_byte = base_offset + chars[..end_char].iter().map(|c| c.len_utf8()).sum::<usize>();
+        spans.push((start_byte, end_byte));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        let mut tokenizer = CodeTokenizer;
+        let mut stream = tokenizer.token_stream(text);
+        let mut out = Vec::new();
+        while let Some(tok) = stream.next() {
+            out.push(tok.text.clone());
+        }
+        out
+    }
+
+    #[test]
+    fn splits_snake_case() {
+        assert_eq!(words("format_user_name"), vec!["format", "user", "name"]);
+    }
+
+    #[test]
+    fn splits_camel_case() {
+        assert_eq!(words("UserService"), vec!["user", "service"]);
+    }
+
+    #[test]
+    fn splits_acronym_boundaries() {
+        assert_eq!(words("HTTPServerError"), vec!["http", "server", "error"]);
+    }
+
+    #[test]
+    fn offsets_point_back_into_source() {
+        let text = "fn format_user_name()";
+        let mut tokenizer = CodeTokenizer;
+        let mut stream = tokenizer.token_stream(text);
+        let first = stream.next().unwrap();
+        assert_eq!(&text[first.offset_from..first.offset_to], "fn");
+    }
+}
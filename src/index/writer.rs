@@ -0,0 +1,337 @@
+//! The index's write side: a background thread owns the Tantivy
+//! `IndexWriter` so callers never block on a commit, with an autocommit
+//! timer that flushes accumulated changes on its own schedule.
+
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{bounded, select, Sender};
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::{doc, Index, IndexReader, ReloadPolicy, Term};
+
+use crate::symbol::Symbol;
+
+use super::schema::{build_schema, fields};
+use super::tokenizer::{CodeTokenizer, CODE_TOKENIZER};
+
+/// Commit if this many documents have been written since the last
+/// commit, without waiting for the autocommit timer.
+const AUTOCOMMIT_MAX_DOCS: usize = 256;
+/// Otherwise, commit at least this often.
+const AUTOCOMMIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A file's current symbol set, as produced by re-parsing it. Applying
+/// one of these deletes every previously indexed document for `path`
+/// and re-adds `symbols`, so callers don't need to compute a diff
+/// themselves.
+pub struct FileUpdate {
+    pub path: PathBuf,
+    pub symbols: Vec<Symbol>,
+}
+
+/// One ranked search result.
+#[derive(Debug)]
+pub struct SearchHit {
+    pub symbol_id: String,
+    pub name: String,
+    pub kind: String,
+    pub module_path: String,
+    pub signature: String,
+    pub span_start: u32,
+    pub span_end: u32,
+    pub score: f32,
+}
+
+enum Command {
+    Apply(FileUpdate),
+    Flush(Sender<()>),
+    Shutdown,
+}
+
+/// A handle to an open symbol index. Writes go through a background
+/// thread; reads go through Tantivy's own `IndexReader`, which reloads
+/// as new commits land.
+pub struct IndexHandle {
+    sender: Sender<Command>,
+    worker: Option<JoinHandle<()>>,
+    reader: IndexReader,
+    index: Index,
+}
+
+impl IndexHandle {
+    /// Open the index at `dir`, creating it (and the directory) if it
+    /// doesn't exist yet.
+    pub fn open_or_create(dir: &Path) -> Result<IndexHandle> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("creating index directory {}", dir.display()))?;
+        let mmap_dir = tantivy::directory::MmapDirectory::open(dir)
+            .with_context(|| format!("opening index directory {}", dir.display()))?;
+        let index = Index::open_or_create(mmap_dir, build_schema())
+            .context("opening or creating the Tantivy index")?;
+        index
+            .tokenizers()
+            .register(CODE_TOKENIZER, CodeTokenizer);
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()?;
+
+        let writer = index
+            .writer(50_000_000)
+            .context("opening the Tantivy index writer")?;
+
+        let (sender, receiver) = bounded::<Command>(1024);
+        let worker_index = index.clone();
+        let worker = std::thread::Builder::new()
+            .name("codeindex-writer".to_string())
+            .spawn(move || run_writer_thread(worker_index, writer, receiver))
+            .context("spawning index writer thread")?;
+
+        Ok(IndexHandle {
+            sender,
+            worker: Some(worker),
+            reader,
+            index,
+        })
+    }
+
+    /// Re-index a single file: delete its previous documents and add
+    /// `symbols` in their place. Applied asynchronously by the writer
+    /// thread and flushed on the autocommit schedule; call
+    /// [`IndexHandle::flush`] to force a synchronous commit.
+    pub fn apply(&self, update: FileUpdate) -> Result<()> {
+        self.sender
+            .send(Command::Apply(update))
+            .context("index writer thread is no longer running")
+    }
+
+    /// Query the index, returning up to `limit` ranked hits across the
+    /// name, signature and doc fields.
+    pub fn search(&self, query_text: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let searcher = self.reader.searcher();
+        let schema = self.index.schema();
+        let name = schema.get_field(fields::NAME)?;
+        let signature = schema.get_field(fields::SIGNATURE)?;
+        let doc_field = schema.get_field(fields::DOC)?;
+
+        let parser = QueryParser::for_index(&self.index, vec![name, signature, doc_field]);
+        let query = parser
+            .parse_query(query_text)
+            .with_context(|| format!("parsing query {query_text:?}"))?;
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit).order_by_score())?;
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, doc_address) in top_docs {
+            let retrieved: tantivy::TantivyDocument = searcher.doc(doc_address)?;
+            hits.push(SearchHit {
+                symbol_id: text_value(&retrieved, &schema, fields::SYMBOL_ID),
+                name: text_value(&retrieved, &schema, fields::NAME),
+                kind: text_value(&retrieved, &schema, fields::KIND),
+                module_path: text_value(&retrieved, &schema, fields::MODULE_PATH),
+                signature: text_value(&retrieved, &schema, fields::SIGNATURE),
+                span_start: u64_value(&retrieved, &schema, fields::SPAN_START) as u32,
+                span_end: u64_value(&retrieved, &schema, fields::SPAN_END) as u32,
+                score,
+            });
+        }
+        Ok(hits)
+    }
+
+    /// Block until every update applied so far has been committed and
+    /// is visible to subsequent searches.
+    pub fn flush(&self) -> Result<()> {
+        let (done_tx, done_rx) = bounded::<()>(0);
+        self.sender
+            .send(Command::Flush(done_tx))
+            .context("index writer thread is no longer running")?;
+        let _ = done_rx.recv();
+        self.reader.reload().context("reloading index reader")
+    }
+}
+
+impl Drop for IndexHandle {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Command::Shutdown);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_writer_thread(
+    index: Index,
+    mut writer: tantivy::IndexWriter,
+    receiver: crossbeam_channel::Receiver<Command>,
+) {
+    let schema = index.schema();
+    let symbol_id = schema.get_field(fields::SYMBOL_ID).unwrap();
+    let file_path = schema.get_field(fields::FILE_PATH).unwrap();
+    let name = schema.get_field(fields::NAME).unwrap();
+    let kind = schema.get_field(fields::KIND).unwrap();
+    let module_path = schema.get_field(fields::MODULE_PATH).unwrap();
+    let signature = schema.get_field(fields::SIGNATURE).unwrap();
+    let doc_field = schema.get_field(fields::DOC).unwrap();
+    let span_start = schema.get_field(fields::SPAN_START).unwrap();
+    let span_end = schema.get_field(fields::SPAN_END).unwrap();
+
+    let ticker = crossbeam_channel::tick(AUTOCOMMIT_INTERVAL);
+    let mut pending_docs = 0usize;
+    // Set by every applied update, including one that only deletes.
+    let mut uncommitted = false;
+
+    loop {
+        select! {
+            recv(receiver) -> msg => {
+                match msg {
+                    Ok(Command::Apply(update)) => {
+                        let path_str = update.path.display().to_string();
+                        writer.delete_term(Term::from_field_text(file_path, &path_str));
+                        uncommitted = true;
+                        for sym in &update.symbols {
+                            pending_docs += 1;
+                            let _ = writer.add_document(doc!(
+                                symbol_id => sym.id.clone(),
+                                file_path => path_str.clone(),
+                                name => sym.name.clone(),
+                                kind => sym.kind.as_str().to_string(),
+                                module_path => sym.module_path.clone(),
+                                signature => sym.signature.clone(),
+                                doc_field => sym.doc.clone(),
+                                span_start => sym.span.start as u64,
+                                span_end => sym.span.end as u64,
+                            ));
+                        }
+                        if pending_docs >= AUTOCOMMIT_MAX_DOCS {
+                            let _ = writer.commit();
+                            pending_docs = 0;
+                            uncommitted = false;
+                        }
+                    }
+                    Ok(Command::Flush(done)) => {
+                        let _ = writer.commit();
+                        pending_docs = 0;
+                        uncommitted = false;
+                        let _ = done.send(());
+                    }
+                    Ok(Command::Shutdown) | Err(_) => {
+                        let _ = writer.commit();
+                        // Dropping the writer releases `.tantivy-writer.lock`.
+                        return;
+                    }
+                }
+            }
+            recv(ticker) -> _ => {
+                if uncommitted {
+                    let _ = writer.commit();
+                    pending_docs = 0;
+                    uncommitted = false;
+                }
+            }
+        }
+    }
+}
+
+fn text_value(doc: &tantivy::TantivyDocument, schema: &tantivy::schema::Schema, field: &str) -> String {
+    use tantivy::schema::Value;
+    let field = schema.get_field(field).unwrap();
+    doc.get_first(field)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn u64_value(doc: &tantivy::TantivyDocument, schema: &tantivy::schema::Schema, field: &str) -> u64 {
+    use tantivy::schema::Value;
+    let field = schema.get_field(field).unwrap();
+    doc.get_first(field).and_then(|v| v.as_u64()).unwrap_or(0)
+}
+
+/// Remove a stale `.tantivy-writer.lock` left behind by a process that
+/// crashed mid-commit. Safe to call on a healthy index: Tantivy
+/// recreates the lock file the next time a writer is opened.
+pub fn unlock(dir: &Path) -> Result<()> {
+    let lock_path = dir.join(".tantivy-writer.lock");
+    if lock_path.exists() {
+        std::fs::remove_file(&lock_path)
+            .with_context(|| format!("removing {}", lock_path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::{Span, SymbolKind};
+
+    fn symbol(name: &str) -> Symbol {
+        Symbol {
+            id: format!("a.rs#{name}"),
+            name: name.to_string(),
+            kind: SymbolKind::Fn,
+            module_path: String::new(),
+            owner: None,
+            trait_impl: None,
+            file: PathBuf::from("a.rs"),
+            signature: format!("fn {name}()"),
+            doc: String::new(),
+            span: Span { start: 0, end: 1 },
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codeindex-writer-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn round_trips_a_symbol_through_apply_flush_search() {
+        let dir = temp_dir("round-trip");
+        let handle = IndexHandle::open_or_create(&dir).unwrap();
+
+        handle
+            .apply(FileUpdate {
+                path: PathBuf::from("a.rs"),
+                symbols: vec![symbol("format_user_name")],
+            })
+            .unwrap();
+        handle.flush().unwrap();
+
+        let hits = handle.search("format_user_name", 10).unwrap();
+        assert!(hits.iter().any(|h| h.name == "format_user_name"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reapplying_a_file_drops_its_previous_symbols() {
+        let dir = temp_dir("reapply");
+        let handle = IndexHandle::open_or_create(&dir).unwrap();
+
+        handle
+            .apply(FileUpdate {
+                path: PathBuf::from("a.rs"),
+                symbols: vec![symbol("old_symbol")],
+            })
+            .unwrap();
+        handle.flush().unwrap();
+        assert!(!handle.search("old_symbol", 10).unwrap().is_empty());
+
+        handle
+            .apply(FileUpdate {
+                path: PathBuf::from("a.rs"),
+                symbols: vec![symbol("new_symbol")],
+            })
+            .unwrap();
+        handle.flush().unwrap();
+
+        assert!(handle.search("old_symbol", 10).unwrap().is_empty());
+        assert!(!handle.search("new_symbol", 10).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
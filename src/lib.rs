@@ -0,0 +1,9 @@
+//! `codeindex` parses Rust source files into symbol tables and keeps a
+//! searchable index of what it finds.
+
+pub mod index;
+pub mod parser;
+pub mod query;
+pub mod reindex;
+pub mod resolve;
+pub mod symbol;
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+use codeindex::index::{unlock, FileUpdate, IndexHandle};
+use codeindex::parser::{parse_file, ParsedFile};
+use codeindex::query;
+use codeindex::reindex::ReindexPool;
+use codeindex::resolve::CallGraph;
+
+#[derive(Parser)]
+#[command(name = "codeindex", about = "Symbol search over a Rust codebase")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create an empty index at INDEX_DIR, if one doesn't already exist.
+    Init { index_dir: PathBuf },
+    /// Parse every `.rs` file under SOURCE_DIR and (re)index it.
+    Refill {
+        index_dir: PathBuf,
+        source_dir: PathBuf,
+    },
+    /// Remove a stale writer lock left behind by a crashed process.
+    Unlock { index_dir: PathBuf },
+    /// Run a free-text query against the index and print ranked hits.
+    Query {
+        index_dir: PathBuf,
+        text: String,
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// List what SYMBOL calls, resolved against SOURCE_DIR.
+    Callees { source_dir: PathBuf, symbol: String },
+    /// List what calls SYMBOL, resolved against SOURCE_DIR.
+    Callers { source_dir: PathBuf, symbol: String },
+    /// Run a structured query (`kind:fn name:user* calls:validate_email`)
+    /// against SOURCE_DIR, resolving it fresh like `callers`/`callees` do
+    /// rather than against a persisted index (see `codeindex::query`'s
+    /// module docs for why).
+    Find { source_dir: PathBuf, expr: String },
+    /// Index SOURCE_DIR, then keep INDEX_DIR up to date by polling for
+    /// changed files and reindexing only what changed.
+    Watch {
+        index_dir: PathBuf,
+        source_dir: PathBuf,
+        /// How often to poll SOURCE_DIR for changes.
+        #[arg(long, default_value_t = 2)]
+        poll_seconds: u64,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Init { index_dir } => {
+            IndexHandle::open_or_create(&index_dir)?;
+            println!("initialized index at {}", index_dir.display());
+        }
+        Command::Refill {
+            index_dir,
+            source_dir,
+        } => refill(&index_dir, &source_dir)?,
+        Command::Unlock { index_dir } => {
+            unlock(&index_dir)?;
+            println!("cleared writer lock at {}", index_dir.display());
+        }
+        Command::Query {
+            index_dir,
+            text,
+            limit,
+        } => {
+            let handle = IndexHandle::open_or_create(&index_dir)?;
+            for hit in handle.search(&text, limit)? {
+                println!(
+                    "{:>6.2}  {:<8} {}::{}  {}",
+                    hit.score, hit.kind, hit.module_path, hit.name, hit.signature
+                );
+            }
+        }
+        Command::Callees { source_dir, symbol } => {
+            let files: Vec<ParsedFile> = parse_source_tree(&source_dir)?
+                .into_iter()
+                .map(|(_, parsed)| parsed)
+                .collect();
+            let graph = CallGraph::build(&files);
+            for edge in graph.callees_of(&symbol) {
+                println!("{:?}\t{:?}\t{}", edge.kind, edge.confidence, edge.callee);
+            }
+        }
+        Command::Callers { source_dir, symbol } => {
+            let files: Vec<ParsedFile> = parse_source_tree(&source_dir)?
+                .into_iter()
+                .map(|(_, parsed)| parsed)
+                .collect();
+            let graph = CallGraph::build(&files);
+            for edge in graph.callers_of(&symbol) {
+                println!("{:?}\t{:?}\t{}", edge.kind, edge.confidence, edge.caller);
+            }
+        }
+        Command::Watch {
+            index_dir,
+            source_dir,
+            poll_seconds,
+        } => watch(&index_dir, &source_dir, Duration::from_secs(poll_seconds))?,
+        Command::Find { source_dir, expr } => {
+            let files: Vec<ParsedFile> = parse_source_tree(&source_dir)?
+                .into_iter()
+                .map(|(_, parsed)| parsed)
+                .collect();
+            let graph = CallGraph::build(&files);
+            let symbols: Vec<_> = files.into_iter().flat_map(|f| f.symbols).collect();
+            let parsed_query = query::parse(&expr).context("parsing query expression")?;
+            for symbol in query::evaluate(&parsed_query, &symbols, &graph) {
+                println!(
+                    "{:<8} {}::{}  {}",
+                    symbol.kind, symbol.module_path, symbol.name, symbol.signature
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse every `.rs` file under `dir`, paired with the path it came from.
+fn parse_source_tree(dir: &Path) -> Result<Vec<(PathBuf, ParsedFile)>> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        .map(|entry| {
+            let path = entry.path().to_path_buf();
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            let parsed = parse_file(&path, &source)
+                .with_context(|| format!("parsing {}", path.display()))?;
+            Ok((path, parsed))
+        })
+        .collect()
+}
+
+/// Index `source_dir` once, then poll it every `interval` for files whose
+/// mtime has moved (or that have disappeared) and hand just those paths
+/// to a [`ReindexPool`], printing how many symbols and files it tracks
+/// after each batch settles.
+fn watch(index_dir: &Path, source_dir: &Path, interval: Duration) -> Result<()> {
+    let handle = IndexHandle::open_or_create(index_dir)?;
+
+    let mut mtimes: HashMap<PathBuf, SystemTime> = HashMap::new();
+    let mut sources = Vec::new();
+    for entry in walkdir::WalkDir::new(source_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+    {
+        let path = entry.path().to_path_buf();
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let parsed = parse_file(&path, &source)
+            .with_context(|| format!("parsing {}", path.display()))?;
+        handle.apply(FileUpdate {
+            path: path.clone(),
+            symbols: parsed.symbols,
+        })?;
+        mtimes.insert(path.clone(), file_mtime(&path));
+        sources.push((path, source));
+    }
+    let pool = ReindexPool::start_default(handle);
+    for (path, source) in sources {
+        pool.seed(path, &source)?;
+    }
+    pool.wait_idle();
+    println!("watching {} ({} files seeded)", source_dir.display(), mtimes.len());
+
+    loop {
+        std::thread::sleep(interval);
+
+        let mut seen = std::collections::HashSet::new();
+        for entry in walkdir::WalkDir::new(source_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        {
+            let path = entry.path().to_path_buf();
+            seen.insert(path.clone());
+            let mtime = file_mtime(&path);
+            if mtimes.get(&path) != Some(&mtime) {
+                mtimes.insert(path.clone(), mtime);
+                pool.notify_changed(path);
+            }
+        }
+        let removed: Vec<PathBuf> = mtimes
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect();
+        for path in removed {
+            mtimes.remove(&path);
+            pool.notify_removed(path);
+        }
+
+        pool.wait_idle();
+        let (symbols, _) = pool.snapshot();
+        println!("reindexed: {} symbols tracked across {} files", symbols.len(), mtimes.len());
+    }
+}
+
+fn file_mtime(path: &Path) -> SystemTime {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn refill(index_dir: &Path, source_dir: &Path) -> Result<()> {
+    let handle = IndexHandle::open_or_create(index_dir)?;
+    let mut symbols = 0;
+    let mut files = 0;
+    for (path, parsed) in parse_source_tree(source_dir)? {
+        symbols += parsed.symbols.len();
+        files += 1;
+        handle.apply(FileUpdate {
+            path,
+            symbols: parsed.symbols,
+        })?;
+    }
+    handle.flush()?;
+    println!("indexed {symbols} symbols across {files} files");
+    Ok(())
+}
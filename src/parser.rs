@@ -0,0 +1,392 @@
+//! Turns a Rust source file into the flat [`Symbol`](crate::symbol::Symbol)
+//! list the index is built from.
+
+use std::path::Path;
+
+use proc_macro2::LineColumn;
+use quote::ToTokens;
+use syn::spanned::Spanned;
+
+use crate::symbol::{Span, Symbol, SymbolKind};
+
+/// A source file's symbol table alongside the AST it was extracted
+/// from, so later passes (the call-graph resolver) can walk function
+/// bodies without re-parsing.
+#[derive(Clone)]
+pub struct ParsedFile {
+    pub symbols: Vec<Symbol>,
+    pub ast: syn::File,
+}
+
+/// Parse a single source file's contents into its symbols and AST.
+///
+/// `path` is recorded on each symbol and used to build its stable id; it
+/// does not need to exist on disk (callers may parse in-memory buffers).
+pub fn parse_file(path: &Path, source: &str) -> syn::Result<ParsedFile> {
+    let ast = syn::parse_file(source)?;
+    let lines = LineIndex::new(source);
+    let mut symbols = Vec::new();
+    walk_items(&ast.items, path, "", None, &lines, &mut symbols);
+    Ok(ParsedFile { symbols, ast })
+}
+
+fn walk_items(
+    items: &[syn::Item],
+    file: &Path,
+    module_path: &str,
+    owner: Option<&str>,
+    lines: &LineIndex,
+    out: &mut Vec<Symbol>,
+) {
+    for item in items {
+        match item {
+            syn::Item::Mod(m) => {
+                let child_path = join_module(module_path, &m.ident.to_string());
+                if let Some((_, items)) = &m.content {
+                    walk_items(items, file, &child_path, None, lines, out);
+                }
+            }
+            syn::Item::Fn(f) => {
+                out.push(symbol_from_fn(
+                    &f.sig,
+                    &f.attrs,
+                    f.span(),
+                    file,
+                    module_path,
+                    owner,
+                    None,
+                    SymbolKind::Fn,
+                    lines,
+                ));
+            }
+            syn::Item::Struct(s) => {
+                out.push(make_symbol(
+                    s.ident.to_string(),
+                    SymbolKind::Struct,
+                    &s.attrs,
+                    s.span(),
+                    file,
+                    module_path,
+                    None,
+                    None,
+                    header_text(lines, s.span(), "{"),
+                    lines,
+                ));
+            }
+            syn::Item::Enum(e) => {
+                out.push(make_symbol(
+                    e.ident.to_string(),
+                    SymbolKind::Enum,
+                    &e.attrs,
+                    e.span(),
+                    file,
+                    module_path,
+                    None,
+                    None,
+                    header_text(lines, e.span(), "{"),
+                    lines,
+                ));
+            }
+            syn::Item::Const(c) => {
+                out.push(make_symbol(
+                    c.ident.to_string(),
+                    SymbolKind::Const,
+                    &c.attrs,
+                    c.span(),
+                    file,
+                    module_path,
+                    None,
+                    None,
+                    header_text(lines, c.span(), "="),
+                    lines,
+                ));
+            }
+            syn::Item::Static(s) => {
+                out.push(make_symbol(
+                    s.ident.to_string(),
+                    SymbolKind::Static,
+                    &s.attrs,
+                    s.span(),
+                    file,
+                    module_path,
+                    None,
+                    None,
+                    header_text(lines, s.span(), "="),
+                    lines,
+                ));
+            }
+            syn::Item::Trait(t) => {
+                let trait_name = t.ident.to_string();
+                out.push(make_symbol(
+                    trait_name.clone(),
+                    SymbolKind::Trait,
+                    &t.attrs,
+                    t.span(),
+                    file,
+                    module_path,
+                    None,
+                    None,
+                    header_text(lines, t.span(), "{"),
+                    lines,
+                ));
+                for trait_item in &t.items {
+                    if let syn::TraitItem::Fn(m) = trait_item {
+                        out.push(symbol_from_fn(
+                            &m.sig,
+                            &m.attrs,
+                            m.span(),
+                            file,
+                            module_path,
+                            Some(&trait_name),
+                            None,
+                            SymbolKind::Method,
+                            lines,
+                        ));
+                    }
+                }
+            }
+            syn::Item::Impl(imp) => {
+                let owner_name = type_name(&imp.self_ty);
+                let trait_name = imp
+                    .trait_
+                    .as_ref()
+                    .and_then(|(path, _)| path.segments.last())
+                    .map(|seg| seg.ident.to_string());
+                for impl_item in &imp.items {
+                    if let syn::ImplItem::Fn(m) = impl_item {
+                        out.push(symbol_from_fn(
+                            &m.sig,
+                            &m.attrs,
+                            m.span(),
+                            file,
+                            module_path,
+                            owner_name.as_deref(),
+                            trait_name.as_deref(),
+                            SymbolKind::Method,
+                            lines,
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn symbol_from_fn(
+    sig: &syn::Signature,
+    attrs: &[syn::Attribute],
+    span: proc_macro2::Span,
+    file: &Path,
+    module_path: &str,
+    owner: Option<&str>,
+    trait_impl: Option<&str>,
+    kind: SymbolKind,
+    lines: &LineIndex,
+) -> Symbol {
+    make_symbol(
+        sig.ident.to_string(),
+        kind,
+        attrs,
+        span,
+        file,
+        module_path,
+        owner,
+        trait_impl,
+        sig.to_token_stream().to_string(),
+        lines,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn make_symbol(
+    name: String,
+    kind: SymbolKind,
+    attrs: &[syn::Attribute],
+    span: proc_macro2::Span,
+    file: &Path,
+    module_path: &str,
+    owner: Option<&str>,
+    trait_impl: Option<&str>,
+    signature: String,
+    lines: &LineIndex,
+) -> Symbol {
+    let owner = owner.map(str::to_string);
+    let qualified_name = match &owner {
+        Some(o) => format!("{o}::{name}"),
+        None => name.clone(),
+    };
+    Symbol {
+        id: Symbol::make_id(file, &qualified_name),
+        name,
+        kind,
+        module_path: module_path.to_string(),
+        owner,
+        trait_impl: trait_impl.map(str::to_string),
+        file: file.to_path_buf(),
+        signature,
+        doc: doc_comment(attrs),
+        span: Span {
+            start: lines.offset(span.start()),
+            end: lines.offset(span.end()),
+        },
+    }
+}
+
+/// Concatenate `///` / `#[doc = "..."]` lines into a single doc string.
+fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        if let syn::Meta::NameValue(nv) = &attr.meta {
+            if let syn::Expr::Lit(expr_lit) = &nv.value {
+                if let syn::Lit::Str(s) = &expr_lit.lit {
+                    lines.push(s.value().trim().to_string());
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn join_module(parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+        child.to_string()
+    } else {
+        format!("{parent}::{child}")
+    }
+}
+
+/// Best-effort single-line signature text: the source slice from an
+/// item's start up to (but not including) the first occurrence of
+/// `stop_at` anywhere in it (nesting is not tracked), collapsed to one
+/// line. Used for items
+/// whose syn node doesn't expose a ready-made signature (structs, enums,
+/// consts, statics).
+fn header_text(lines: &LineIndex, span: proc_macro2::Span, stop_at: &str) -> String {
+    let start = lines.offset(span.start()) as usize;
+    let end = lines.offset(span.end()) as usize;
+    let text = &lines.source[start..end.min(lines.source.len())];
+    let head = text.split(stop_at).next().unwrap_or(text);
+    head.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Maps `proc_macro2` line/column positions (1-based line, 0-based
+/// column, as produced with the `span-locations` feature) to byte
+/// offsets into the original source string.
+struct LineIndex<'a> {
+    source: &'a str,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<u32>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0u32];
+        for (i, b) in source.bytes().enumerate() {
+            if b == b'\n' {
+                line_starts.push((i + 1) as u32);
+            }
+        }
+        LineIndex {
+            source,
+            line_starts,
+        }
+    }
+
+    fn offset(&self, pos: LineColumn) -> u32 {
+        let line_start = self
+            .line_starts
+            .get(pos.line.saturating_sub(1))
+            .copied()
+            .unwrap_or(0) as usize;
+        let line_end = self
+            .line_starts
+            .get(pos.line)
+            .copied()
+            .map(|o| o as usize)
+            .unwrap_or(self.source.len());
+        // `pos.column` is a *character* count, not a byte count, so a
+        // multibyte char earlier on the line would otherwise throw the
+        // offset off (and can land `header_text`'s slice mid-codepoint).
+        let byte_offset: usize = self.source[line_start..line_end]
+            .chars()
+            .take(pos.column)
+            .map(char::len_utf8)
+            .sum();
+        (line_start + byte_offset) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(symbols: &[Symbol]) -> Vec<String> {
+        symbols.iter().map(Symbol::qualified_name).collect()
+    }
+
+    #[test]
+    fn parses_sample_code() {
+        let source = std::fs::read_to_string("examples/sample-code.rs").unwrap();
+        let parsed = parse_file(Path::new("examples/sample-code.rs"), &source).unwrap();
+
+        assert!(names(&parsed.symbols).contains(&"User::is_valid".to_string()));
+        assert!(names(&parsed.symbols).contains(&"validate_email".to_string()));
+        assert!(names(&parsed.symbols).contains(&"Validator::validate".to_string()));
+
+        let user_struct = parsed
+            .symbols
+            .iter()
+            .find(|s| s.name == "User" && s.kind == SymbolKind::Struct)
+            .unwrap();
+        assert_eq!(&source[user_struct.span.start as usize..user_struct.span.end as usize][..6], "pub st");
+    }
+
+    #[test]
+    fn records_module_path_for_nested_modules() {
+        let source = "mod example { pub fn inner() {} }";
+        let parsed = parse_file(Path::new("lib.rs"), source).unwrap();
+        assert_eq!(parsed.symbols[0].module_path, "example");
+    }
+
+    #[test]
+    fn collects_doc_comments() {
+        let source = "/// Does a thing.\npub fn documented() {}";
+        let parsed = parse_file(Path::new("lib.rs"), source).unwrap();
+        assert_eq!(parsed.symbols[0].doc, "Does a thing.");
+    }
+
+    #[test]
+    fn spans_are_byte_offsets_not_char_offsets_on_multibyte_lines() {
+        let source = "const NAME: &str = \"café\"; struct Point { x: i32 }";
+        let parsed = parse_file(Path::new("lib.rs"), source).unwrap();
+        let point = parsed.symbols.iter().find(|s| s.name == "Point").unwrap();
+        assert_eq!(
+            &source[point.span.start as usize..point.span.end as usize],
+            &source[source.find("struct Point").unwrap()..]
+        );
+    }
+
+    #[test]
+    fn records_trait_impl_on_methods() {
+        let source = "struct User; trait Validator { fn validate(&self); } impl Validator for User { fn validate(&self) {} }";
+        let parsed = parse_file(Path::new("lib.rs"), source).unwrap();
+        let method = parsed
+            .symbols
+            .iter()
+            .find(|s| s.owner.as_deref() == Some("User"))
+            .unwrap();
+        assert_eq!(method.trait_impl.as_deref(), Some("Validator"));
+    }
+}
@@ -0,0 +1,50 @@
+//! The query DSL's AST: boolean combinators over leaf predicates.
+
+use crate::symbol::SymbolKind;
+
+/// A parsed query expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+    Leaf(Predicate),
+}
+
+/// A single field predicate, or the default free-text match when no
+/// field prefix is given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Predicate {
+    /// A bare term with no `field:` prefix: matched against name,
+    /// signature and doc text.
+    Text(Pattern),
+    Name(Pattern),
+    Kind(SymbolKind),
+    Module(String),
+    /// `calls:target` - symbols that call `target`.
+    Calls(QualifiedTarget),
+    /// `calledby:target` - symbols that `target` calls.
+    CalledBy(QualifiedTarget),
+    /// `implements:Trait` - types with an `impl Trait for ...`.
+    Implements(String),
+}
+
+/// How a name pattern should be matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// Matches a whole word/sub-word exactly (case-insensitive).
+    Exact(String),
+    /// `user*` - matches anything starting with `user` (case-insensitive).
+    Prefix(String),
+    /// A quoted phrase - matches as a case-insensitive substring.
+    Phrase(String),
+}
+
+/// An `identifier` or `identifier@module` reference used by
+/// relationship predicates, e.g. `calls:validate_email` or
+/// `calls:add_user@example`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualifiedTarget {
+    pub name: String,
+    pub module: Option<String>,
+}
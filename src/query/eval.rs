@@ -0,0 +1,258 @@
+//! Lowers a parsed [`Query`] into a result set: field predicates are
+//! matched against the symbol table, relationship predicates against
+//! the call graph, and boolean nodes combine child result sets.
+//!
+//! Both tables are the in-memory ones produced by parsing and resolving
+//! the tree fresh (see [`super`]'s module docs for why this doesn't go
+//! through the persisted index), so `evaluate` takes `&[Symbol]` and
+//! `&CallGraph` directly rather than an `IndexHandle`.
+
+use std::collections::HashSet;
+
+use crate::index::tokenizer::CodeTokenizer;
+use crate::resolve::CallGraph;
+use crate::symbol::Symbol;
+use tantivy::tokenizer::{TokenStream, Tokenizer};
+
+use super::ast::{Pattern, Predicate, QualifiedTarget, Query};
+
+/// Run `query` against `symbols`, resolving `calls:`/`calledby:` against
+/// `graph`. Returns matching symbols in their original order.
+pub fn evaluate<'a>(query: &Query, symbols: &'a [Symbol], graph: &CallGraph) -> Vec<&'a Symbol> {
+    let matched = eval_node(query, symbols, graph);
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| matched.contains(i))
+        .map(|(_, s)| s)
+        .collect()
+}
+
+fn eval_node(query: &Query, symbols: &[Symbol], graph: &CallGraph) -> HashSet<usize> {
+    match query {
+        Query::Leaf(predicate) => eval_predicate(predicate, symbols, graph),
+        Query::And(children) => {
+            let mut sets = children.iter().map(|c| eval_node(c, symbols, graph));
+            let Some(first) = sets.next() else {
+                return HashSet::new();
+            };
+            sets.fold(first, |acc, next| acc.intersection(&next).copied().collect())
+        }
+        Query::Or(children) => children
+            .iter()
+            .fold(HashSet::new(), |mut acc, c| {
+                acc.extend(eval_node(c, symbols, graph));
+                acc
+            }),
+        Query::Not(inner) => {
+            let matched = eval_node(inner, symbols, graph);
+            (0..symbols.len()).filter(|i| !matched.contains(i)).collect()
+        }
+    }
+}
+
+fn eval_predicate(predicate: &Predicate, symbols: &[Symbol], graph: &CallGraph) -> HashSet<usize> {
+    match predicate {
+        Predicate::Kind(kind) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.kind == *kind)
+            .map(|(i, _)| i)
+            .collect(),
+        Predicate::Module(module) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| &s.module_path == module)
+            .map(|(i, _)| i)
+            .collect(),
+        Predicate::Name(pattern) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| matches_pattern(pattern, &s.name))
+            .map(|(i, _)| i)
+            .collect(),
+        Predicate::Text(pattern) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                matches_pattern_subwords(pattern, &s.name)
+                    || matches_pattern_subwords(pattern, &s.signature)
+                    || matches_pattern_subwords(pattern, &s.doc)
+            })
+            .map(|(i, _)| i)
+            .collect(),
+        Predicate::Implements(trait_name) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                symbols
+                    .iter()
+                    .any(|m| m.owner.as_deref() == Some(&s.name) && m.trait_impl.as_deref() == Some(trait_name))
+            })
+            .map(|(i, _)| i)
+            .collect(),
+        Predicate::Calls(target) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                graph
+                    .callees_of(&s.qualified_name())
+                    .iter()
+                    .any(|edge| target_matches(target, &edge.callee, symbols))
+            })
+            .map(|(i, _)| i)
+            .collect(),
+        Predicate::CalledBy(target) => symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                graph
+                    .callers_of(&s.qualified_name())
+                    .iter()
+                    .any(|edge| target_matches(target, &edge.caller, symbols))
+            })
+            .map(|(i, _)| i)
+            .collect(),
+    }
+}
+
+/// Does `qualified_name` (a call edge's caller or callee) refer to
+/// `target`? Without a module, match on the name alone (qualified or
+/// bare); with one, only accept a symbol that's actually declared in
+/// that module.
+fn target_matches(target: &QualifiedTarget, qualified_name: &str, symbols: &[Symbol]) -> bool {
+    match &target.module {
+        None => qualified_name == target.name || qualified_name.ends_with(&format!("::{}", target.name)),
+        Some(module) => symbols.iter().any(|s| {
+            s.module_path == *module
+                && (s.name == target.name || s.qualified_name() == target.name)
+                && s.qualified_name() == qualified_name
+        }),
+    }
+}
+
+fn matches_pattern(pattern: &Pattern, text: &str) -> bool {
+    let text_lower = text.to_lowercase();
+    match pattern {
+        Pattern::Exact(value) => text_lower == value.to_lowercase(),
+        Pattern::Prefix(prefix) => text_lower.starts_with(&prefix.to_lowercase()),
+        Pattern::Phrase(phrase) => text_lower.contains(&phrase.to_lowercase()),
+    }
+}
+
+/// Like [`matches_pattern`], but against the `snake_case`/`CamelCase`
+/// sub-words of `text` rather than the whole string, so `name:user*`-
+/// style patterns used as free text still find `format_user_name`.
+fn matches_pattern_subwords(pattern: &Pattern, text: &str) -> bool {
+    if let Pattern::Phrase(phrase) = pattern {
+        return text.to_lowercase().contains(&phrase.to_lowercase());
+    }
+    let mut tokenizer = CodeTokenizer;
+    let mut stream = tokenizer.token_stream(text);
+    let mut words = Vec::new();
+    while let Some(tok) = TokenStream::next(&mut stream) {
+        words.push(tok.text.clone());
+    }
+    match pattern {
+        Pattern::Exact(value) => words.iter().any(|w| w == &value.to_lowercase()),
+        Pattern::Prefix(prefix) => words.iter().any(|w| w.starts_with(&prefix.to_lowercase())),
+        Pattern::Phrase(_) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::parser::parse_file;
+    use crate::symbol::SymbolKind;
+
+    use super::super::parse;
+    use super::*;
+
+    fn sample() -> (Vec<Symbol>, CallGraph) {
+        let source = std::fs::read_to_string("examples/sample-code.rs").unwrap();
+        let parsed = parse_file(Path::new("examples/sample-code.rs"), &source).unwrap();
+        let graph = CallGraph::build(std::slice::from_ref(&parsed));
+        (parsed.symbols, graph)
+    }
+
+    fn names(query: &str, symbols: &[Symbol], graph: &CallGraph) -> Vec<String> {
+        evaluate(&parse(query).unwrap(), symbols, graph)
+            .into_iter()
+            .map(Symbol::qualified_name)
+            .collect()
+    }
+
+    #[test]
+    fn kind_predicate_matches_only_that_kind() {
+        let (symbols, graph) = sample();
+        let hits = names("kind:struct", &symbols, &graph);
+        assert!(hits.contains(&"User".to_string()));
+        assert!(hits.contains(&"Point".to_string()));
+        assert!(symbols
+            .iter()
+            .filter(|s| hits.contains(&s.qualified_name()))
+            .all(|s| s.kind == SymbolKind::Struct));
+    }
+
+    #[test]
+    fn name_prefix_matches_by_identifier_prefix_not_subword() {
+        let (symbols, graph) = sample();
+        // Matches the start of the whole identifier, case-insensitively
+        // (User, UserService, ...) rather than a subword match anywhere
+        // inside it (which `create_user` would also satisfy).
+        let hits = names("name:user*", &symbols, &graph);
+        assert!(hits.contains(&"User".to_string()));
+        assert!(hits.contains(&"UserService".to_string()));
+        assert!(!hits.contains(&"create_user".to_string()));
+
+        let hits = names("name:create*", &symbols, &graph);
+        assert_eq!(hits, vec!["create_user".to_string()]);
+    }
+
+    #[test]
+    fn module_predicate_matches_top_level_items() {
+        let (symbols, graph) = sample();
+        let hits = names("module:", &symbols, &graph);
+        assert!(hits.contains(&"User".to_string()));
+    }
+
+    #[test]
+    fn calls_predicate_finds_callers_of_the_target() {
+        let (symbols, graph) = sample();
+        let hits = names("calls:validate_email", &symbols, &graph);
+        assert!(hits.contains(&"User::is_valid".to_string()));
+        assert!(hits.contains(&"create_user".to_string()));
+    }
+
+    #[test]
+    fn calledby_predicate_finds_callees_of_the_target() {
+        let (symbols, graph) = sample();
+        let hits = names("calledby:create_user", &symbols, &graph);
+        assert!(hits.contains(&"validate_email".to_string()));
+    }
+
+    #[test]
+    fn implements_predicate_finds_the_implementing_type() {
+        let (symbols, graph) = sample();
+        let hits = names("implements:Validator", &symbols, &graph);
+        assert!(hits.contains(&"User".to_string()));
+    }
+
+    #[test]
+    fn and_or_not_combine_as_expected() {
+        let (symbols, graph) = sample();
+
+        let and_hits = names("kind:struct name:point*", &symbols, &graph);
+        assert_eq!(and_hits, vec!["Point".to_string()]);
+
+        let or_hits = names("kind:struct OR kind:trait", &symbols, &graph);
+        assert!(or_hits.contains(&"User".to_string()));
+        assert!(or_hits.contains(&"Validator".to_string()));
+
+        let not_hits = names("kind:struct NOT name:point*", &symbols, &graph);
+        assert!(not_hits.contains(&"User".to_string()));
+        assert!(!not_hits.contains(&"Point".to_string()));
+    }
+}
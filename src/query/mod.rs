@@ -0,0 +1,23 @@
+//! A small query language over the symbol table and call graph:
+//! `kind:fn name:user* calls:validate_email implements:Validator` parses
+//! into an [`ast::Query`] AST and [`evaluate`] lowers it into a result
+//! set, matching field predicates against an in-memory `&[Symbol]` and
+//! relationship predicates against a [`crate::resolve::CallGraph`].
+//!
+//! This deliberately evaluates against a freshly parsed symbol table and
+//! call graph rather than the persisted Tantivy index from
+//! [`crate::index`], the same way the `callers`/`callees` CLI commands
+//! already do: `calls`/`calledby`/`implements` predicates need the call
+//! graph, which isn't persisted in the index, so splitting field
+//! predicates off to hit the index while relationship predicates hit an
+//! in-memory graph would mean every query still pays to parse and
+//! resolve the tree, just to answer half its predicates a different
+//! way. The persisted index remains the `query` command's job.
+
+pub mod ast;
+mod eval;
+mod parser;
+
+pub use ast::{Pattern, Predicate, Query, QualifiedTarget};
+pub use eval::evaluate;
+pub use parser::{parse, QueryParseError};
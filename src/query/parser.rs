@@ -0,0 +1,285 @@
+//! Parses query DSL strings like `kind:fn name:user* calls:validate_email`
+//! into a [`Query`] AST.
+//!
+//! Grammar (terms separated by whitespace are implicitly ANDed):
+//!
+//! ```text
+//! query      := or_expr
+//! or_expr    := and_expr ("OR" and_expr)*
+//! and_expr   := not_expr ("AND"? not_expr)*
+//! not_expr   := ("NOT" | "-") not_expr | atom
+//! atom       := "(" or_expr ")" | term
+//! term       := [field ":"] (word | "*"-suffixed word) | quoted phrase
+//! ```
+//!
+//! A quoted phrase is always matched as free text; it takes no field
+//! prefix.
+
+use thiserror::Error;
+
+use crate::symbol::SymbolKind;
+
+use super::ast::{Predicate, Query, QualifiedTarget, Pattern};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum QueryParseError {
+    #[error("unexpected end of query")]
+    UnexpectedEnd,
+    #[error("expected closing ')'")]
+    UnmatchedParen,
+    #[error("unterminated quoted phrase")]
+    UnterminatedQuote,
+    #[error("unknown symbol kind {0:?}")]
+    UnknownKind(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    Or,
+    Not,
+    Term { text: String, quoted: bool },
+}
+
+pub fn parse(input: &str) -> Result<Query, QueryParseError> {
+    let tokens = lex(input)?;
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let query = parser.parse_or()?;
+    // `parse_or` only stops early at a `)` with no matching `(`.
+    if parser.pos < tokens.len() {
+        return Err(QueryParseError::UnmatchedParen);
+    }
+    Ok(query)
+}
+
+fn lex(input: &str) -> Result<Vec<Token>, QueryParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(_, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        match c {
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                let mut closed = false;
+                for (_, c) in chars.by_ref() {
+                    if c == '"' {
+                        closed = true;
+                        break;
+                    }
+                    text.push(c);
+                }
+                if !closed {
+                    return Err(QueryParseError::UnterminatedQuote);
+                }
+                tokens.push(Token::Term { text, quoted: true });
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                match word.to_ascii_uppercase().as_str() {
+                    "OR" => tokens.push(Token::Or),
+                    "AND" => {} // implicit between adjacent terms
+                    "NOT" => tokens.push(Token::Not),
+                    _ => tokens.push(Token::Term { text: word, quoted: false }),
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<&Token> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    fn parse_or(&mut self) -> Result<Query, QueryParseError> {
+        let mut terms = vec![self.parse_and()?];
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.advance();
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.pop().unwrap() } else { Query::Or(terms) })
+    }
+
+    fn parse_and(&mut self) -> Result<Query, QueryParseError> {
+        let mut terms = vec![self.parse_not()?];
+        while matches!(self.peek(), Some(Token::Not) | Some(Token::LParen) | Some(Token::Term { .. })) {
+            terms.push(self.parse_not()?);
+        }
+        Ok(if terms.len() == 1 { terms.pop().unwrap() } else { Query::And(terms) })
+    }
+
+    fn parse_not(&mut self) -> Result<Query, QueryParseError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.advance();
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Query, QueryParseError> {
+        match self.advance() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err(QueryParseError::UnmatchedParen),
+                }
+            }
+            Some(Token::Term { text, quoted }) => parse_predicate(text, *quoted).map(Query::Leaf),
+            Some(Token::RParen) => Err(QueryParseError::UnmatchedParen),
+            Some(Token::Or) | Some(Token::Not) | None => Err(QueryParseError::UnexpectedEnd),
+        }
+    }
+}
+
+fn parse_predicate(text: &str, quoted: bool) -> Result<Predicate, QueryParseError> {
+    if !quoted {
+        if let Some((field, value)) = text.split_once(':') {
+            match field {
+                "kind" => {
+                    return SymbolKind::parse(value)
+                        .map(Predicate::Kind)
+                        .ok_or_else(|| QueryParseError::UnknownKind(value.to_string()));
+                }
+                "name" => return Ok(Predicate::Name(parse_pattern(value, false))),
+                "module" => return Ok(Predicate::Module(value.to_string())),
+                "calls" => return Ok(Predicate::Calls(parse_qualified(value))),
+                "calledby" => return Ok(Predicate::CalledBy(parse_qualified(value))),
+                "implements" => return Ok(Predicate::Implements(value.to_string())),
+                _ => {}
+            }
+        }
+    }
+    Ok(Predicate::Text(parse_pattern(text, quoted)))
+}
+
+fn parse_pattern(value: &str, quoted: bool) -> Pattern {
+    if quoted {
+        Pattern::Phrase(value.to_string())
+    } else if let Some(prefix) = value.strip_suffix('*') {
+        Pattern::Prefix(prefix.to_string())
+    } else {
+        Pattern::Exact(value.to_string())
+    }
+}
+
+fn parse_qualified(value: &str) -> QualifiedTarget {
+    match value.split_once('@') {
+        Some((name, module)) => QualifiedTarget {
+            name: name.to_string(),
+            module: Some(module.to_string()),
+        },
+        None => QualifiedTarget {
+            name: value.to_string(),
+            module: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_implicit_and_of_field_predicates() {
+        let query = parse("kind:fn name:user*").unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Leaf(Predicate::Kind(SymbolKind::Fn)),
+                Query::Leaf(Predicate::Name(Pattern::Prefix("user".to_string()))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_qualified_call_target() {
+        let query = parse("calls:add_user@example").unwrap();
+        assert_eq!(
+            query,
+            Query::Leaf(Predicate::Calls(QualifiedTarget {
+                name: "add_user".to_string(),
+                module: Some("example".to_string()),
+            }))
+        );
+    }
+
+    #[test]
+    fn parses_or_and_not() {
+        let query = parse("kind:struct OR NOT kind:enum").unwrap();
+        assert_eq!(
+            query,
+            Query::Or(vec![
+                Query::Leaf(Predicate::Kind(SymbolKind::Struct)),
+                Query::Not(Box::new(Query::Leaf(Predicate::Kind(SymbolKind::Enum)))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_parenthesized_grouping() {
+        let query = parse("(kind:fn OR kind:method) name:validate*").unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Or(vec![
+                    Query::Leaf(Predicate::Kind(SymbolKind::Fn)),
+                    Query::Leaf(Predicate::Kind(SymbolKind::Method)),
+                ]),
+                Query::Leaf(Predicate::Name(Pattern::Prefix("validate".to_string()))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_quoted_phrase_as_default_text_field() {
+        let query = parse("\"format user\"").unwrap();
+        assert_eq!(
+            query,
+            Query::Leaf(Predicate::Text(Pattern::Phrase("format user".to_string())))
+        );
+    }
+
+    #[test]
+    fn bare_term_defaults_to_text_predicate() {
+        let query = parse("validate").unwrap();
+        assert_eq!(query, Query::Leaf(Predicate::Text(Pattern::Exact("validate".to_string()))));
+    }
+}
@@ -0,0 +1,381 @@
+//! Incremental reindexing: instead of rescanning the whole tree, a small
+//! background worker pool re-parses one changed file at a time and
+//! applies just that file's update to the symbol index; the call graph
+//! is marked stale and rebuilt crate-wide the next time it is read.
+//!
+//! Callers drive this with [`ReindexPool::notify_changed`] and
+//! [`ReindexPool::notify_removed`] — this module doesn't watch the
+//! filesystem itself, the same way [`crate::index`] doesn't decide when
+//! a file changed, only what to do once it has. Rapid repeated edits to
+//! the same path coalesce into a single job; [`ReindexPool::wait_idle`]
+//! blocks until every queued job has settled, for deterministic
+//! querying after a batch of edits.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{bounded, Receiver, Sender};
+
+use crate::index::{FileUpdate, IndexHandle};
+use crate::parser::parse_file;
+use crate::resolve::CallGraph;
+use crate::symbol::Symbol;
+
+/// Worker threads in the pool when none is requested explicitly.
+const DEFAULT_WORKERS: usize = 4;
+
+enum Job {
+    Changed(PathBuf),
+    Removed(PathBuf),
+}
+
+/// A snapshot of how busy the pool is right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PoolStatus {
+    /// Jobs waiting to be picked up by a worker.
+    pub queued: usize,
+    /// Jobs a worker is actively re-parsing or applying.
+    pub in_flight: usize,
+}
+
+impl PoolStatus {
+    pub fn is_idle(&self) -> bool {
+        self.queued == 0 && self.in_flight == 0
+    }
+}
+
+/// The state the pool tracks between jobs. Source text (not the parsed
+/// `syn::File`) is what's kept around: the AST isn't `Send`, so rather
+/// than pin it in memory across worker threads, a changed file's call
+/// graph contribution is recomputed by re-parsing every file's cached
+/// source. That's still strictly less I/O than a whole-tree rescan,
+/// since nothing gets re-read from disk except the file that actually
+/// changed — but re-resolving the whole crate-wide graph from scratch
+/// is real CPU work, so it's deferred (see `mark_graph_dirty`) rather
+/// than redone after every single job.
+#[derive(Default)]
+struct Store {
+    sources: HashMap<PathBuf, String>,
+    symbols: HashMap<PathBuf, Vec<Symbol>>,
+    graph: CallGraph,
+    graph_dirty: bool,
+}
+
+impl Store {
+    /// A file's symbols or source changed. The call graph is crate-wide
+    /// (a symbol added to one file can resolve a call site in another),
+    /// so there's no cheaper per-file patch for it — but jobs for *other*
+    /// files don't need it recomputed between each of them, only readers
+    /// do. Mark it stale and let `ensure_graph` pay for the rebuild once,
+    /// lazily, the next time something actually asks for the graph. This
+    /// turns an O(N) resolve per job (O(N^2) across an N-file import
+    /// batch) into one O(N) resolve no matter how many jobs ran since the
+    /// last read, the same way the index writer defers its Tantivy
+    /// commit to a flush instead of committing after every `apply`.
+    fn mark_graph_dirty(&mut self) {
+        self.graph_dirty = true;
+    }
+
+    fn ensure_graph(&mut self) -> &CallGraph {
+        if self.graph_dirty {
+            let parsed = self
+                .sources
+                .iter()
+                .filter_map(|(path, source)| parse_file(path, source).ok())
+                .collect::<Vec<_>>();
+            self.graph = CallGraph::build(&parsed);
+            self.graph_dirty = false;
+        }
+        &self.graph
+    }
+}
+
+/// Background worker pool that turns file-change notifications into
+/// minimal updates against an [`IndexHandle`] and a [`CallGraph`].
+pub struct ReindexPool {
+    // `Option` so `Drop` can take and drop the sender *before* joining the
+    // workers: they exit their `for job in receiver.iter()` loop only once
+    // every `Sender` is gone, and a struct's own fields aren't dropped
+    // until after its `Drop::drop` body returns.
+    job_sender: Option<Sender<Job>>,
+    pending: Arc<Mutex<HashSet<PathBuf>>>,
+    busy: Arc<(Mutex<usize>, Condvar)>,
+    store: Arc<Mutex<Store>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ReindexPool {
+    /// Start a pool of `workers` threads applying reindex jobs against
+    /// `handle`. `handle` is moved in since only the pool's workers
+    /// should be writing to it once reindexing is live.
+    pub fn start(handle: IndexHandle, workers: usize) -> ReindexPool {
+        let (job_sender, job_receiver) = bounded::<Job>(1024);
+        let pending = Arc::new(Mutex::new(HashSet::new()));
+        let busy = Arc::new((Mutex::new(0usize), Condvar::new()));
+        let store = Arc::new(Mutex::new(Store::default()));
+        let handle = Arc::new(handle);
+
+        let pool_workers = (0..workers.max(1))
+            .map(|n| {
+                spawn_worker(
+                    n,
+                    job_receiver.clone(),
+                    Arc::clone(&pending),
+                    Arc::clone(&busy),
+                    Arc::clone(&store),
+                    Arc::clone(&handle),
+                )
+            })
+            .collect();
+
+        ReindexPool {
+            job_sender: Some(job_sender),
+            pending,
+            busy,
+            store,
+            workers: pool_workers,
+        }
+    }
+
+    /// Start a pool with [`DEFAULT_WORKERS`] threads.
+    pub fn start_default(handle: IndexHandle) -> ReindexPool {
+        ReindexPool::start(handle, DEFAULT_WORKERS)
+    }
+
+    /// Seed the pool's store with a file's current source, without
+    /// going through the job queue. Use this to prime the pool from a
+    /// whole-tree scan before switching over to incremental
+    /// notifications.
+    pub fn seed(&self, path: PathBuf, source: &str) -> Result<()> {
+        let parsed =
+            parse_file(&path, source).with_context(|| format!("parsing {}", path.display()))?;
+        let mut store = self.store.lock().unwrap();
+        store.symbols.insert(path.clone(), parsed.symbols);
+        store.sources.insert(path, source.to_string());
+        store.mark_graph_dirty();
+        Ok(())
+    }
+
+    /// Schedule a reindex of `path`. If a job for `path` is already
+    /// queued, this is a no-op: the queued job will pick up whatever is
+    /// on disk when a worker gets to it, so repeated rapid edits to the
+    /// same path still only cost one job.
+    pub fn notify_changed(&self, path: PathBuf) {
+        self.enqueue(Job::Changed(path));
+    }
+
+    /// Schedule removal of `path` from the index and call graph.
+    pub fn notify_removed(&self, path: PathBuf) {
+        self.enqueue(Job::Removed(path));
+    }
+
+    fn enqueue(&self, job: Job) {
+        let path = match &job {
+            Job::Changed(p) | Job::Removed(p) => p.clone(),
+        };
+        let mut pending = self.pending.lock().unwrap();
+        if !pending.insert(path) {
+            return;
+        }
+        drop(pending);
+        *self.busy.0.lock().unwrap() += 1;
+        // A full queue blocks here until a worker takes a job; the send
+        // only fails once every receiver is gone.
+        if let Some(sender) = &self.job_sender {
+            let _ = sender.send(job);
+        }
+    }
+
+    /// Block until every job scheduled so far has been applied.
+    pub fn wait_idle(&self) {
+        let (lock, cvar) = &*self.busy;
+        let guard = lock.lock().unwrap();
+        drop(cvar.wait_while(guard, |count| *count > 0).unwrap());
+    }
+
+    /// How many jobs are queued or being worked right now.
+    pub fn status(&self) -> PoolStatus {
+        let in_flight = *self.busy.0.lock().unwrap();
+        let queued = self.pending.lock().unwrap().len();
+        PoolStatus {
+            queued,
+            in_flight: in_flight.saturating_sub(queued),
+        }
+    }
+
+    /// A consistent snapshot of every symbol currently tracked and the
+    /// call graph resolved against them, for running queries against
+    /// live state between batches of edits.
+    pub fn snapshot(&self) -> (Vec<Symbol>, CallGraph) {
+        let mut store = self.store.lock().unwrap();
+        let graph = store.ensure_graph().clone();
+        let symbols = store.symbols.values().flatten().cloned().collect();
+        (symbols, graph)
+    }
+}
+
+impl Drop for ReindexPool {
+    fn drop(&mut self) {
+        // Drop the sender now, not whenever the field would otherwise go
+        // out of scope (after this function returns): each worker's
+        // `receiver.iter()` only ends once every `Sender` is gone, so
+        // joining first would deadlock against a sender we're still
+        // holding.
+        self.job_sender.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_worker(
+    n: usize,
+    receiver: Receiver<Job>,
+    pending: Arc<Mutex<HashSet<PathBuf>>>,
+    busy: Arc<(Mutex<usize>, Condvar)>,
+    store: Arc<Mutex<Store>>,
+    handle: Arc<IndexHandle>,
+) -> JoinHandle<()> {
+    std::thread::Builder::new()
+        .name(format!("codeindex-reindex-{n}"))
+        .spawn(move || {
+            for job in receiver.iter() {
+                let path = match &job {
+                    Job::Changed(p) | Job::Removed(p) => p.clone(),
+                };
+                // Unpending before processing lets a fresh edit to the
+                // same path enqueue its own job instead of being
+                // dropped as a duplicate of the one we're about to run.
+                pending.lock().unwrap().remove(&path);
+                run_job(job, &path, &store, &handle);
+                let (lock, cvar) = &*busy;
+                let mut count = lock.lock().unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    cvar.notify_all();
+                }
+            }
+        })
+        .expect("spawning reindex worker thread")
+}
+
+fn run_job(job: Job, path: &Path, store: &Mutex<Store>, handle: &IndexHandle) {
+    let symbols = match job {
+        Job::Changed(_) => {
+            let Ok(source) = std::fs::read_to_string(path) else {
+                return;
+            };
+            let Ok(parsed) = parse_file(path, &source) else {
+                return;
+            };
+            let symbols = parsed.symbols;
+            let mut store = store.lock().unwrap();
+            store.sources.insert(path.to_path_buf(), source);
+            store.symbols.insert(path.to_path_buf(), symbols.clone());
+            store.mark_graph_dirty();
+            symbols
+        }
+        Job::Removed(_) => {
+            let mut store = store.lock().unwrap();
+            store.sources.remove(path);
+            store.symbols.remove(path);
+            store.mark_graph_dirty();
+            Vec::new()
+        }
+    };
+    let _ = handle.apply(FileUpdate {
+        path: path.to_path_buf(),
+        symbols,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codeindex-reindex-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_file(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn notify_changed_then_removed_updates_snapshot() {
+        let src_dir = temp_dir("basic-src");
+        let idx_dir = temp_dir("basic-idx");
+        let handle = IndexHandle::open_or_create(&idx_dir).unwrap();
+        let pool = ReindexPool::start_default(handle);
+
+        let path = write_file(&src_dir, "a.rs", "pub fn alpha() -> i32 { 1 }");
+        pool.seed(path.clone(), &std::fs::read_to_string(&path).unwrap())
+            .unwrap();
+        pool.wait_idle();
+
+        let (symbols, _) = pool.snapshot();
+        assert!(symbols.iter().any(|s| s.name == "alpha"));
+
+        std::fs::write(
+            &path,
+            "pub fn alpha() -> i32 { 1 }\npub fn beta() -> i32 { alpha() }",
+        )
+        .unwrap();
+        pool.notify_changed(path.clone());
+        pool.wait_idle();
+
+        let (symbols, graph) = pool.snapshot();
+        assert!(symbols.iter().any(|s| s.name == "beta"));
+        assert!(graph.callees_of("beta").iter().any(|e| e.callee == "alpha"));
+
+        std::fs::remove_file(&path).unwrap();
+        pool.notify_removed(path.clone());
+        pool.wait_idle();
+
+        let (symbols, _) = pool.snapshot();
+        assert!(symbols.is_empty());
+
+        let _ = std::fs::remove_dir_all(&src_dir);
+        let _ = std::fs::remove_dir_all(&idx_dir);
+    }
+
+    #[test]
+    fn rapid_edits_to_the_same_path_coalesce_and_settle_on_the_latest_content() {
+        let src_dir = temp_dir("coalesce-src");
+        let idx_dir = temp_dir("coalesce-idx");
+        let handle = IndexHandle::open_or_create(&idx_dir).unwrap();
+        let pool = ReindexPool::start(handle, 1);
+
+        let path = write_file(&src_dir, "a.rs", "pub fn v0() {}");
+        pool.seed(path.clone(), &std::fs::read_to_string(&path).unwrap())
+            .unwrap();
+        pool.wait_idle();
+
+        for n in 1..20 {
+            std::fs::write(&path, format!("pub fn v{n}() {{}}")).unwrap();
+            pool.notify_changed(path.clone());
+        }
+        let status = pool.status();
+        assert!(
+            status.queued + status.in_flight < 19,
+            "rapid edits to the same path should coalesce into far fewer jobs, got {status:?}"
+        );
+
+        pool.wait_idle();
+        let (symbols, _) = pool.snapshot();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "v19");
+
+        let _ = std::fs::remove_dir_all(&src_dir);
+        let _ = std::fs::remove_dir_all(&idx_dir);
+    }
+}
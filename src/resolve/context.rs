@@ -0,0 +1,183 @@
+//! Builds the per-scope symbol tables the resolver walks outward
+//! through: crate-wide free functions, per-type inherent and
+//! trait-impl methods, and `use` aliases.
+
+use std::collections::HashMap;
+
+use crate::symbol::{Symbol, SymbolKind};
+
+/// A type's resolvable methods, split the way method resolution in Rust
+/// itself is: inherent methods shadow trait methods of the same name.
+#[derive(Default)]
+pub struct TypeInfo {
+    /// method name -> qualified name (`Type::method`)
+    pub inherent: HashMap<String, String>,
+    /// method name -> (trait name, qualified trait declaration name)
+    pub trait_methods: HashMap<String, (String, String)>,
+}
+
+/// The symbol tables used to resolve call expressions, built once from
+/// every parsed file before the AST is walked for call sites.
+#[derive(Default)]
+pub struct ResolveContext {
+    /// free function name -> qualified names defining it (usually one;
+    /// more than one means the name is ambiguous across modules)
+    free_fns: HashMap<String, Vec<String>>,
+    types: HashMap<String, TypeInfo>,
+    /// import alias -> the name it stands for
+    use_aliases: HashMap<String, String>,
+}
+
+/// What a call resolved to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    Free,
+    Method,
+    TraitMethod,
+}
+
+/// How confident the resolver is in a [`CallKind`]/callee pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// Exactly one candidate matched.
+    Resolved,
+    /// More than one candidate matched (e.g. two free functions with
+    /// the same name in different modules); `callee` names one of them.
+    Ambiguous,
+    /// No candidate matched, or the receiver's type wasn't known
+    /// statically; `callee` is just the called name.
+    Unresolved,
+}
+
+pub struct Resolution {
+    pub callee: String,
+    pub kind: CallKind,
+    pub confidence: Confidence,
+}
+
+impl ResolveContext {
+    pub fn build<'a>(files: impl IntoIterator<Item = &'a crate::parser::ParsedFile>) -> ResolveContext {
+        let mut ctx = ResolveContext::default();
+        for file in files {
+            for symbol in &file.symbols {
+                ctx.add_symbol(symbol);
+            }
+            collect_use_aliases(&file.ast.items, &mut ctx.use_aliases);
+        }
+        ctx
+    }
+
+    fn add_symbol(&mut self, symbol: &Symbol) {
+        match (&symbol.kind, &symbol.owner) {
+            (SymbolKind::Fn, None) => {
+                self.free_fns
+                    .entry(symbol.name.clone())
+                    .or_default()
+                    .push(symbol.qualified_name());
+            }
+            (SymbolKind::Method, Some(owner)) => {
+                let type_info = self.types.entry(owner.clone()).or_default();
+                match &symbol.trait_impl {
+                    None => {
+                        type_info
+                            .inherent
+                            .insert(symbol.name.clone(), symbol.qualified_name());
+                    }
+                    Some(trait_name) => {
+                        type_info.trait_methods.insert(
+                            symbol.name.clone(),
+                            (trait_name.clone(), format!("{trait_name}::{}", symbol.name)),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Resolve a bare call like `validate_email(...)`, honoring any
+    /// `use`-import alias on `name`.
+    pub fn resolve_free_call(&self, name: &str) -> Resolution {
+        let real_name = self.use_aliases.get(name).map(String::as_str).unwrap_or(name);
+        match self.free_fns.get(real_name) {
+            None => Resolution {
+                callee: name.to_string(),
+                kind: CallKind::Free,
+                confidence: Confidence::Unresolved,
+            },
+            Some(candidates) if candidates.len() == 1 => Resolution {
+                callee: candidates[0].clone(),
+                kind: CallKind::Free,
+                confidence: Confidence::Resolved,
+            },
+            Some(candidates) => Resolution {
+                callee: candidates[0].clone(),
+                kind: CallKind::Free,
+                confidence: Confidence::Ambiguous,
+            },
+        }
+    }
+
+    /// Resolve `receiver.method(...)` or `Type::method(...)` once the
+    /// receiver/type is known: inherent methods win over trait methods
+    /// of the same name, matching Rust's own method resolution order.
+    pub fn resolve_method_call(&self, type_name: &str, method: &str) -> Resolution {
+        let Some(type_info) = self.types.get(type_name) else {
+            return Resolution {
+                callee: method.to_string(),
+                kind: CallKind::Method,
+                confidence: Confidence::Unresolved,
+            };
+        };
+        if let Some(qualified) = type_info.inherent.get(method) {
+            return Resolution {
+                callee: qualified.clone(),
+                kind: CallKind::Method,
+                confidence: Confidence::Resolved,
+            };
+        }
+        if let Some((_, qualified)) = type_info.trait_methods.get(method) {
+            return Resolution {
+                callee: qualified.clone(),
+                kind: CallKind::TraitMethod,
+                confidence: Confidence::Resolved,
+            };
+        }
+        Resolution {
+            callee: method.to_string(),
+            kind: CallKind::Method,
+            confidence: Confidence::Unresolved,
+        }
+    }
+}
+
+/// Walk `use` trees (including inline `mod`s) collecting `as`-renames,
+/// e.g. `use foo::Bar as Baz;` records `Baz -> Bar`.
+fn collect_use_aliases(items: &[syn::Item], aliases: &mut HashMap<String, String>) {
+    for item in items {
+        match item {
+            syn::Item::Use(u) => collect_use_tree(&u.tree, aliases),
+            syn::Item::Mod(m) => {
+                if let Some((_, items)) = &m.content {
+                    collect_use_aliases(items, aliases);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_use_tree(tree: &syn::UseTree, aliases: &mut HashMap<String, String>) {
+    match tree {
+        syn::UseTree::Path(p) => collect_use_tree(&p.tree, aliases),
+        syn::UseTree::Rename(r) => {
+            aliases.insert(r.rename.to_string(), r.ident.to_string());
+        }
+        syn::UseTree::Group(g) => {
+            for tree in &g.items {
+                collect_use_tree(tree, aliases);
+            }
+        }
+        syn::UseTree::Name(_) | syn::UseTree::Glob(_) => {}
+    }
+}
@@ -0,0 +1,125 @@
+//! Resolves call expressions in parsed source against the symbols
+//! they're defined alongside, producing a call graph that tells free
+//! function calls, inherent-method calls and trait-method calls apart
+//! instead of matching on name alone.
+
+mod context;
+mod visitor;
+
+pub use context::{CallKind, Confidence};
+
+use crate::parser::ParsedFile;
+
+/// One resolved (or not) call site.
+#[derive(Debug, Clone)]
+pub struct CallEdge {
+    pub caller: String,
+    pub callee: String,
+    pub kind: CallKind,
+    pub confidence: Confidence,
+}
+
+/// A queryable call graph: which symbol calls which, in both
+/// directions.
+#[derive(Default, Clone)]
+pub struct CallGraph {
+    edges: Vec<CallEdge>,
+}
+
+impl CallGraph {
+    /// Build the call graph for a set of already-parsed files. Name
+    /// resolution is crate-wide: a call in one file can resolve to a
+    /// symbol declared in another.
+    pub fn build(files: &[ParsedFile]) -> CallGraph {
+        let ctx = context::ResolveContext::build(files);
+        let mut edges = Vec::new();
+        for file in files {
+            let mut collector = visitor::CallCollector::new(&ctx);
+            syn::visit::Visit::visit_file(&mut collector, &file.ast);
+            edges.extend(collector.edges);
+        }
+        CallGraph { edges }
+    }
+
+    /// Every call edge out of `caller` (a qualified name, e.g.
+    /// `UserService::add_user` or `validate_email`).
+    pub fn callees_of(&self, caller: &str) -> Vec<&CallEdge> {
+        self.edges.iter().filter(|e| e.caller == caller).collect()
+    }
+
+    /// Every call edge into `callee`.
+    pub fn callers_of(&self, callee: &str) -> Vec<&CallEdge> {
+        self.edges.iter().filter(|e| e.callee == callee).collect()
+    }
+
+    pub fn edges(&self) -> &[CallEdge] {
+        &self.edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_file;
+    use std::path::Path;
+
+    fn build_sample() -> CallGraph {
+        let source = std::fs::read_to_string("examples/sample-code.rs").unwrap();
+        let parsed = parse_file(Path::new("examples/sample-code.rs"), &source).unwrap();
+        CallGraph::build(&[parsed])
+    }
+
+    #[test]
+    fn resolves_free_function_call() {
+        let graph = build_sample();
+        let callees = graph.callees_of("User::is_valid");
+        let validate_email = callees.iter().find(|e| e.callee == "validate_email").unwrap();
+        assert_eq!(validate_email.kind, CallKind::Free);
+        assert_eq!(validate_email.confidence, Confidence::Resolved);
+    }
+
+    #[test]
+    fn resolves_inherent_method_call_on_known_receiver() {
+        let graph = build_sample();
+        let callees = graph.callees_of("process_users");
+        let add_user = callees.iter().find(|e| e.callee == "UserService::add_user").unwrap();
+        assert_eq!(add_user.kind, CallKind::Method);
+        assert_eq!(add_user.confidence, Confidence::Resolved);
+    }
+
+    #[test]
+    fn resolves_free_function_call_from_a_method_body() {
+        let graph = build_sample();
+        let callees = graph.callees_of("User::format_name");
+        let format_user_name = callees
+            .iter()
+            .find(|e| e.callee == "format_user_name")
+            .unwrap();
+        assert_eq!(format_user_name.kind, CallKind::Free);
+        assert_eq!(format_user_name.confidence, Confidence::Resolved);
+    }
+
+    #[test]
+    fn resolves_trait_method_call_through_impl() {
+        let source = "
+            struct User;
+            trait Validator { fn validate(&self) -> bool; }
+            impl Validator for User { fn validate(&self) -> bool { true } }
+            fn check(user: &User) -> bool { user.validate() }
+        ";
+        let parsed = parse_file(Path::new("lib.rs"), source).unwrap();
+        let graph = CallGraph::build(&[parsed]);
+        let callees = graph.callees_of("check");
+        let validate = callees.iter().find(|e| e.callee == "Validator::validate").unwrap();
+        assert_eq!(validate.kind, CallKind::TraitMethod);
+        assert_eq!(validate.confidence, Confidence::Resolved);
+    }
+
+    #[test]
+    fn callers_of_reports_the_reverse_direction() {
+        let graph = build_sample();
+        let callers = graph.callers_of("validate_email");
+        assert!(callers.iter().any(|e| e.caller == "User::is_valid"));
+        assert!(callers.iter().any(|e| e.caller == "create_user"));
+    }
+}
@@ -0,0 +1,174 @@
+//! Walks function/method bodies looking for call expressions, tracking
+//! just enough local type information (`self`, typed parameters, and
+//! `let x = Type::new(...)`-style constructor calls) to resolve method
+//! receivers when they're statically obvious.
+
+use std::collections::HashMap;
+
+use syn::visit::{self, Visit};
+
+use super::context::{CallKind, Confidence, ResolveContext};
+use super::CallEdge;
+
+pub struct CallCollector<'ctx> {
+    ctx: &'ctx ResolveContext,
+    pub edges: Vec<CallEdge>,
+    current_caller: String,
+    current_self_type: Option<String>,
+    var_types: HashMap<String, String>,
+}
+
+impl<'ctx> CallCollector<'ctx> {
+    pub fn new(ctx: &'ctx ResolveContext) -> Self {
+        CallCollector {
+            ctx,
+            edges: Vec::new(),
+            current_caller: String::new(),
+            current_self_type: None,
+            var_types: HashMap::new(),
+        }
+    }
+
+    fn enter_fn(&mut self, caller: String, self_type: Option<String>, sig: &syn::Signature) {
+        self.current_caller = caller;
+        self.current_self_type = self_type;
+        self.var_types.clear();
+        for input in &sig.inputs {
+            if let syn::FnArg::Typed(pat_type) = input {
+                if let syn::Pat::Ident(ident) = &*pat_type.pat {
+                    if let Some(ty) = type_name(&pat_type.ty) {
+                        self.var_types.insert(ident.ident.to_string(), ty);
+                    }
+                }
+            }
+        }
+    }
+
+    fn infer_receiver_type(&self, expr: &syn::Expr) -> Option<String> {
+        match expr {
+            syn::Expr::Path(p) if p.path.is_ident("self") => self.current_self_type.clone(),
+            syn::Expr::Path(p) => p
+                .path
+                .get_ident()
+                .and_then(|ident| self.var_types.get(&ident.to_string()).cloned()),
+            syn::Expr::Reference(r) => self.infer_receiver_type(&r.expr),
+            syn::Expr::Paren(p) => self.infer_receiver_type(&p.expr),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, resolution: super::context::Resolution) {
+        self.edges.push(CallEdge {
+            caller: self.current_caller.clone(),
+            callee: resolution.callee,
+            kind: resolution.kind,
+            confidence: resolution.confidence,
+        });
+    }
+}
+
+impl<'ast, 'ctx> Visit<'ast> for CallCollector<'ctx> {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.enter_fn(node.sig.ident.to_string(), None, &node.sig);
+        visit::visit_item_fn(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let self_type = type_name(&node.self_ty);
+        let saved = self.current_self_type.clone();
+        self.current_self_type = self_type;
+        visit::visit_item_impl(self, node);
+        self.current_self_type = saved;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        let owner = self.current_self_type.clone();
+        let caller = match &owner {
+            Some(owner) => format!("{owner}::{}", node.sig.ident),
+            None => node.sig.ident.to_string(),
+        };
+        self.enter_fn(caller, owner, &node.sig);
+        visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        if node.default.is_some() {
+            self.enter_fn(node.sig.ident.to_string(), None, &node.sig);
+        }
+        visit::visit_trait_item_fn(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        if let syn::Pat::Ident(ident) = &node.pat {
+            if let Some(init) = &node.init {
+                if let Some(ty) = constructor_type(&init.expr) {
+                    self.var_types.insert(ident.ident.to_string(), ty);
+                }
+            }
+        } else if let syn::Pat::Type(pat_type) = &node.pat {
+            if let syn::Pat::Ident(ident) = &*pat_type.pat {
+                if let Some(ty) = type_name(&pat_type.ty) {
+                    self.var_types.insert(ident.ident.to_string(), ty);
+                }
+            }
+        }
+        visit::visit_local(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(p) = &*node.func {
+            let segments: Vec<String> = p.path.segments.iter().map(|s| s.ident.to_string()).collect();
+            let resolution = match segments.as_slice() {
+                [name] => self.ctx.resolve_free_call(name),
+                [.., ty, method] => self.ctx.resolve_method_call(ty, method),
+                [] => super::context::Resolution {
+                    callee: String::new(),
+                    kind: CallKind::Free,
+                    confidence: Confidence::Unresolved,
+                },
+            };
+            if !resolution.callee.is_empty() {
+                self.push(resolution);
+            }
+        }
+        visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        let resolution = match self.infer_receiver_type(&node.receiver) {
+            Some(ty) => self.ctx.resolve_method_call(&ty, &method),
+            None => super::context::Resolution {
+                callee: method,
+                kind: CallKind::Method,
+                confidence: Confidence::Unresolved,
+            },
+        };
+        self.push(resolution);
+        visit::visit_expr_method_call(self, node);
+    }
+}
+
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Reference(r) => type_name(&r.elem),
+        syn::Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Best-effort guess that `let x = Type::assoc_fn(...)` binds `x: Type`,
+/// true for constructors like `UserService::new()` and for anything
+/// else that happens to follow the same `Type::fn(...)` shape.
+fn constructor_type(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Call(call) => match &*call.func {
+            syn::Expr::Path(p) if p.path.segments.len() >= 2 => {
+                let second_to_last = p.path.segments.len() - 2;
+                Some(p.path.segments[second_to_last].ident.to_string())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,101 @@
+//! Symbol table types shared by the parser and the index.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The kind of a parsed symbol, mirroring the item kinds the parser
+/// recognizes in a Rust source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Fn,
+    Method,
+    Struct,
+    Enum,
+    Trait,
+    Const,
+    Static,
+}
+
+impl SymbolKind {
+    /// The lowercase name used as the `kind` field value in the index and
+    /// in query strings (e.g. `kind:fn`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolKind::Fn => "fn",
+            SymbolKind::Method => "method",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Const => "const",
+            SymbolKind::Static => "static",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<SymbolKind> {
+        match s {
+            "fn" => Some(SymbolKind::Fn),
+            "method" => Some(SymbolKind::Method),
+            "struct" => Some(SymbolKind::Struct),
+            "enum" => Some(SymbolKind::Enum),
+            "trait" => Some(SymbolKind::Trait),
+            "const" => Some(SymbolKind::Const),
+            "static" => Some(SymbolKind::Static),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A byte-offset span into the source file a symbol was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A symbol extracted from a source file: a function, method, struct,
+/// trait, enum, const or static item, along with enough context to
+/// disambiguate it from same-named symbols elsewhere in the tree.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// Stable identifier, unique within a file, used to key index
+    /// documents so re-parsing a file can delete-then-reinsert cleanly.
+    pub id: String,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// `::`-separated module path the symbol is declared in, e.g. `example::inner`.
+    pub module_path: String,
+    /// For methods, the name of the enclosing `impl` or `trait` type.
+    pub owner: Option<String>,
+    /// For a method defined in `impl Trait for Type`, the trait's name;
+    /// `None` for inherent methods, free functions and trait method
+    /// declarations themselves.
+    pub trait_impl: Option<String>,
+    pub file: PathBuf,
+    pub signature: String,
+    pub doc: String,
+    pub span: Span,
+}
+
+impl Symbol {
+    /// The fully qualified name used for disambiguation, e.g.
+    /// `UserService::add_user` for a method or `validate_email` for a
+    /// free function.
+    pub fn qualified_name(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{owner}::{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Build the stable per-file symbol id from the file path and
+    /// qualified name.
+    pub fn make_id(file: &Path, qualified_name: &str) -> String {
+        format!("{}#{}", file.display(), qualified_name)
+    }
+}